@@ -0,0 +1,192 @@
+// Stochastic solver: fill each box with a random permutation of its
+// missing digits, then hill-climb towards zero row/column duplicates via
+// simulated annealing (Metropolis acceptance, geometric cooling). Useful
+// as an anytime alternative when the deterministic propagation in
+// `make_forced_choices` would otherwise explore a huge search tree.
+
+use super::{Board, Coord, Number, Space};
+use super::rng::Rng;
+use std::time::{Duration, Instant};
+
+const START_TEMPERATURE: f64 = 1.0;
+const COOLING_RATE: f64 = 0.9999;
+const STAGNATION_LIMIT: u32 = 50_000;
+const TIME_BUDGET: Duration = Duration::from_secs(30);
+
+struct State {
+    board: Board,
+    given: [bool; 81],
+}
+
+impl State {
+    fn init(board: Board, rng: &mut Rng) -> Self {
+        let mut given = [false; 81];
+        for coord in Coord::all(3) {
+            if let Space::Full(_) = board[coord] {
+                given[coord.0 as usize] = true;
+            }
+        }
+
+        let mut board = board;
+        for square in 0..9u8 {
+            let cells = square_cells(square);
+
+            let mut used = [false; 10];
+            for &c in &cells {
+                if let Space::Full(n) = board[c] {
+                    used[n.get() as usize] = true;
+                }
+            }
+            let mut missing: Vec<u8> = (1..=9).filter(|&d| !used[d as usize]).collect();
+            rng.shuffle(&mut missing);
+
+            let mut fill_values = missing.into_iter();
+            for &c in &cells {
+                if board[c] == Space::Empty {
+                    board[c] = Space::Full(Number::new(fill_values.next().unwrap()));
+                }
+            }
+        }
+
+        Self { board, given }
+    }
+
+    fn calc_score(&self) -> u32 {
+        let mut score = 0;
+        for y in 0..9 {
+            score += self.row_score(y);
+        }
+        for x in 0..9 {
+            score += self.col_score(x);
+        }
+        score
+    }
+
+    fn row_score(&self, y: u16) -> u32 {
+        Self::region_duplicates((0..9u16).map(|x| self.board[Coord::classic(x, y)]))
+    }
+
+    fn col_score(&self, x: u16) -> u32 {
+        Self::region_duplicates((0..9u16).map(|y| self.board[Coord::classic(x, y)]))
+    }
+
+    fn region_duplicates(region: impl Iterator<Item = Space>) -> u32 {
+        let mut counts = [0u32; 10];
+        for space in region {
+            if let Space::Full(n) = space {
+                counts[n.get() as usize] += 1;
+            }
+        }
+        counts.iter().map(|&c| c.saturating_sub(1)).sum()
+    }
+
+    // swaps the two cells, returning the change in total duplicate count;
+    // only the (at most four) affected rows/columns are rescored
+    fn swap_and_score_delta(&mut self, a: Coord, b: Coord) -> i64 {
+        let rows: &[u16] = if a.y() == b.y() { &[a.y()] } else { &[a.y(), b.y()] };
+        let cols: &[u16] = if a.x() == b.x() { &[a.x()] } else { &[a.x(), b.x()] };
+
+        let before: u32 = rows.iter().map(|&y| self.row_score(y)).sum::<u32>()
+            + cols.iter().map(|&x| self.col_score(x)).sum::<u32>();
+
+        let tmp = self.board[a];
+        self.board[a] = self.board[b];
+        self.board[b] = tmp;
+
+        let after: u32 = rows.iter().map(|&y| self.row_score(y)).sum::<u32>()
+            + cols.iter().map(|&x| self.col_score(x)).sum::<u32>();
+
+        after as i64 - before as i64
+    }
+
+    fn open_cells_in_square(&self, square: u8) -> Vec<Coord> {
+        square_cells(square)
+            .into_iter()
+            .filter(|c| !self.given[c.0 as usize])
+            .collect()
+    }
+}
+
+fn square_cells(square: u8) -> Vec<Coord> {
+    let start_x = 3u16 * (square % 3) as u16;
+    let start_y = 3u16 * (square / 3) as u16;
+    let mut cells = Vec::with_capacity(9);
+    for dy in 0..3u16 {
+        for dx in 0..3u16 {
+            cells.push(Coord::classic(start_x + dx, start_y + dy));
+        }
+    }
+    cells
+}
+
+/// Anytime stochastic solver: fills the givens' boxes with random
+/// permutations and swaps non-given cells within a box to drive row/column
+/// duplicates to zero, accepting worsening moves per the Metropolis
+/// criterion and reheating (fresh restart) on stagnation.
+pub fn solve_annealing(board: Board) -> Option<Board> {
+    let mut rng = Rng::seeded();
+    let deadline = Instant::now() + TIME_BUDGET;
+
+    loop {
+        let mut state = State::init(board.clone(), &mut rng);
+        let mut score = state.calc_score();
+        let mut temperature = START_TEMPERATURE;
+        let mut stagnant = 0;
+
+        while score > 0 {
+            if Instant::now() >= deadline {
+                return None;
+            }
+
+            let square = rng.gen_range(9) as u8;
+            let cells = state.open_cells_in_square(square);
+            if cells.len() < 2 {
+                continue;
+            }
+            let i = rng.gen_range(cells.len());
+            let mut j = rng.gen_range(cells.len());
+            while j == i {
+                j = rng.gen_range(cells.len());
+            }
+            let (a, b) = (cells[i], cells[j]);
+
+            let delta = state.swap_and_score_delta(a, b);
+            let accept = delta <= 0 || rng.next_f64() < (-(delta as f64) / temperature).exp();
+            if accept {
+                score = (score as i64 + delta) as u32;
+                stagnant = if delta < 0 { 0 } else { stagnant + 1 };
+            } else {
+                let tmp = state.board[a];
+                state.board[a] = state.board[b];
+                state.board[b] = tmp;
+                stagnant += 1;
+            }
+
+            temperature *= COOLING_RATE;
+            if stagnant > STAGNATION_LIMIT {
+                break;
+            }
+        }
+
+        if score == 0 {
+            return Some(state.board);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::solve::solve;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000028000419005000080079";
+
+    #[test]
+    fn agrees_with_backtracking_solver() {
+        let board: Board = PUZZLE.parse().unwrap();
+        let expected = solve(board.clone()).unwrap();
+        let actual = solve_annealing(board).unwrap();
+        assert_eq!(actual.to_line_string(), expected.to_line_string());
+    }
+}
@@ -0,0 +1,84 @@
+// A growable bitset backed by Vec<u64>, so boards whose cell count exceeds
+// 128 (any box dimension b >= 4) still get O(1)-ish set/clear/union and a
+// cheap iterator over set bits, the way the old single-u128 open-spaces
+// mask did for the fixed 9x9 board.
+
+#[derive(Clone)]
+pub struct Bitset {
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    pub fn new(len: usize) -> Self {
+        Self { words: vec![0; len.div_ceil(64)] }
+    }
+
+    pub fn full(len: usize) -> Self {
+        let mut bits = Self::new(len);
+        for i in 0..len {
+            bits.set(i);
+        }
+        bits
+    }
+
+    pub fn set(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    pub fn clear(&mut self, i: usize) {
+        self.words[i / 64] &= !(1 << (i % 64));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear_all(&mut self) {
+        for w in &mut self.words {
+            *w = 0;
+        }
+    }
+
+    pub fn union_with(&mut self, other: &Bitset) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &Bitset) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    pub fn iter(&self) -> BitsetIter {
+        BitsetIter { words: self.words.clone(), word_idx: 0 }
+    }
+}
+
+pub struct BitsetIter {
+    words: Vec<u64>,
+    word_idx: usize,
+}
+
+impl Iterator for BitsetIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word_idx < self.words.len() {
+            let w = self.words[self.word_idx];
+            if w == 0 {
+                self.word_idx += 1;
+                continue;
+            }
+            let bit = w.trailing_zeros() as usize;
+            self.words[self.word_idx] &= w - 1;
+            return Some(self.word_idx * 64 + bit);
+        }
+        None
+    }
+}
@@ -1,4 +1,5 @@
 use std::num::NonZeroU8;
+use std::str::FromStr;
 
 #[derive(Copy, Clone, PartialEq)]
 pub struct Number(NonZeroU8);
@@ -7,7 +8,7 @@ impl Number {
     pub fn safe_new(n: u8) -> Option<Self> {
         Some(Self(NonZeroU8::new(n)?))
     }
-    
+
     pub fn new(n: u8) -> Self {
         Self::safe_new(n).unwrap()
     }
@@ -26,75 +27,197 @@ pub enum Space {
 impl std::fmt::Display for Space {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Empty => write!(f, " "),
+            Self::Empty => write!(f, "."),
             Self::Full(num) => write!(f, "{}", num.0.get())
         }
     }
 }
 
+// A coordinate on a board with box dimension `b` (side = b*b). `b` rides
+// along with the raw index so x()/y()/square() stay self-contained
+// instead of needing the board's dimension threaded through every call.
 #[derive(Copy, Clone)]
-pub struct Coord(pub(in super) u8);
+pub struct Coord(pub(in super) u16, pub(in super) u8);
 
 impl Coord {
-    pub fn new(x: u8, y: u8) -> Self {
-        Self(y * 9 + x)   
+    pub fn new(x: u16, y: u16, b: u8) -> Self {
+        let side = b as u16 * b as u16;
+        Self(y * side + x, b)
+    }
+
+    // convenience constructor for the classic 9x9 (b = 3) board
+    pub fn classic(x: u16, y: u16) -> Self {
+        Self::new(x, y, 3)
     }
 
-    pub fn all() -> impl Iterator<Item=Self> {
-        (0..81).map(Coord)
+    pub fn all(b: u8) -> impl Iterator<Item=Self> {
+        let side = b as u16 * b as u16;
+        let cells = side * side;
+        (0..cells).map(move |i| Self(i, b))
     }
 
-    pub fn x(&self) -> u8 {
-        self.0 % 9
+    pub fn b(&self) -> u8 {
+        self.1
     }
-    
-    pub fn y(&self) -> u8 {
-        self.0 / 9
+
+    pub fn side(&self) -> u16 {
+        self.1 as u16 * self.1 as u16
+    }
+
+    pub fn x(&self) -> u16 {
+        self.0 % self.side()
     }
 
-    pub fn square(&self) -> u8 {
-        3 * (self.y() / 3) + (self.x() / 3)
+    pub fn y(&self) -> u16 {
+        self.0 / self.side()
+    }
+
+    pub fn square(&self) -> u16 {
+        let b = self.1 as u16;
+        b * (self.y() / b) + (self.x() / b)
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct Board([Space; 81]);
+#[derive(Clone)]
+pub struct Board {
+    b: u8,
+    spaces: Vec<Space>,
+}
 
 impl Board {
+    // classic 9x9 board (b = 3)
     pub fn new() -> Self {
-        Self([Space::Empty; 81])
+        Self::with_box_size(3)
+    }
+
+    pub fn with_box_size(b: u8) -> Self {
+        let side = b as usize * b as usize;
+        Self { b, spaces: vec![Space::Empty; side * side] }
+    }
+
+    pub fn b(&self) -> u8 {
+        self.b
+    }
+
+    pub fn side(&self) -> u16 {
+        self.b as u16 * self.b as u16
+    }
+
+    pub fn cells(&self) -> usize {
+        self.spaces.len()
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item=Coord> {
+        Coord::all(self.b)
+    }
+
+    /// The canonical 81-character line form (digits `1`-`9`, `0` for
+    /// empty) that `FromStr` parses back into an equal board.
+    ///
+    /// Only defined for the classic 9x9 board (`b() == 3`), since that's
+    /// the only size `FromStr` round-trips; larger boards can hold cell
+    /// values that don't fit in a single digit.
+    pub fn to_line_string(&self) -> String {
+        assert_eq!(self.b(), 3, "to_line_string only supports the classic 9x9 board");
+        self.coords()
+            .map(|coord| match self[coord] {
+                Space::Empty => '0',
+                Space::Full(n) => std::char::from_digit(n.get() as u32, 10).unwrap(),
+            })
+            .collect()
     }
 }
 
 impl std::ops::Index<Coord> for Board {
     type Output = Space;
     fn index(&self, index: Coord) -> &Space {
-        &self.0[index.0 as usize]
+        &self.spaces[index.0 as usize]
     }
 }
 
 impl std::ops::IndexMut<Coord> for Board {
     fn index_mut(&mut self, index: Coord) -> &mut Space {
-        &mut self.0[index.0 as usize]
+        &mut self.spaces[index.0 as usize]
+    }
+}
+
+/// The common 81-character line format (digits `1`-`9`, `0` or `.` for
+/// empty) and the tolerant multi-line grid form `Display` prints (ignoring
+/// `|`, `-`, `+` and whitespace) both parse into a classic 9x9 board.
+#[derive(Debug)]
+pub struct ParseBoardError;
+
+impl std::fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected 81 sudoku cells (digits 1-9, '0' or '.' for empty)")
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+impl FromStr for Board {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells: Vec<char> = s.chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '|' | '-' | '+'))
+            .collect();
+
+        if cells.len() != 81 {
+            return Err(ParseBoardError);
+        }
+
+        let mut board = Board::new();
+        for (coord, ch) in board.coords().zip(cells) {
+            board[coord] = match ch {
+                '0' | '.' => Space::Empty,
+                '1'..='9' => Space::Full(Number::new(ch.to_digit(10).unwrap() as u8)),
+                _ => return Err(ParseBoardError),
+            };
+        }
+        Ok(board)
     }
 }
 
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..9 {
-            for x in 0..9 {
-                write!(f, "{}", self[Coord::new(x, y)])?;
-                if x == 2 || x == 5 {
+        let b = self.b as u16;
+        let side = self.side();
+        // cells can hold values up to `side`, so pad each one to however
+        // many digits that takes (e.g. "16" on a 16x16 board) so adjacent
+        // cells stay visually distinguishable
+        let width = side.to_string().len();
+        for y in 0..side {
+            for x in 0..side {
+                write!(f, "{:>width$}", self[Coord::new(x, y, self.b)].to_string(), width = width)?;
+                if x != side - 1 && (x + 1) % b == 0 {
                     write!(f, "|")?;
                 }
             }
-            if y != 8 {
+            if y != side - 1 {
                 write!(f, "\n")?;
             }
-            if y == 2 || y == 5 {
-                write!(f, "---+---+---\n")?;
+            if y != side - 1 && (y + 1) % b == 0 {
+                let row_width = side as usize * width + (side / b - 1) as usize;
+                writeln!(f, "{}", "-".repeat(row_width))?;
             }
         }
         return Ok(());
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000028000419005000080079";
+
+    #[test]
+    fn display_output_round_trips_through_parse() {
+        let board: Board = PUZZLE.parse().unwrap();
+        let printed = format!("{}", board);
+        let reparsed: Board = printed.parse().unwrap();
+        assert_eq!(reparsed.to_line_string(), board.to_line_string());
+    }
+}
@@ -0,0 +1,248 @@
+// Exact-cover solver: Algorithm X over a sparse matrix represented as
+// circular doubly-linked lists ("dancing links"). Columns are the 324
+// constraints (cell-occupied, row-has-digit, col-has-digit, box-has-digit)
+// and rows are the 729 possible (Coord, Number) placements.
+
+use super::{Board, Coord, Number, Space};
+
+const NUM_CELLS: usize = 81;
+const NUM_COLS: usize = NUM_CELLS * 4;
+const NUM_ROWS: usize = NUM_CELLS * 9;
+const ROOT: usize = NUM_COLS;
+
+#[derive(Clone, Copy)]
+struct DlxNode {
+    left: usize,
+    right: usize,
+    up: usize,
+    down: usize,
+    column: usize,
+    row_id: usize,
+}
+
+struct Dlx {
+    nodes: Vec<DlxNode>,
+    size: Vec<usize>,
+    row_start: Vec<usize>,
+}
+
+impl Dlx {
+    fn new() -> Self {
+        let mut nodes = Vec::with_capacity(NUM_COLS + 1);
+        for col in 0..NUM_COLS {
+            nodes.push(DlxNode {
+                left: if col == 0 { ROOT } else { col - 1 },
+                right: if col == NUM_COLS - 1 { ROOT } else { col + 1 },
+                up: col,
+                down: col,
+                column: col,
+                row_id: usize::MAX,
+            });
+        }
+        nodes.push(DlxNode {
+            left: NUM_COLS - 1,
+            right: 0,
+            up: ROOT,
+            down: ROOT,
+            column: ROOT,
+            row_id: usize::MAX,
+        });
+
+        Self { nodes, size: vec![0; NUM_COLS], row_start: vec![0; NUM_ROWS] }
+    }
+
+    fn add_row(&mut self, row_id: usize, cols: [usize; 4]) {
+        let mut first: Option<usize> = None;
+        let mut prev: Option<usize> = None;
+        for &col in &cols {
+            let idx = self.nodes.len();
+            let above = self.nodes[col].up;
+            self.nodes.push(DlxNode { left: idx, right: idx, up: above, down: col, column: col, row_id });
+            self.nodes[above].down = idx;
+            self.nodes[col].up = idx;
+            self.size[col] += 1;
+
+            if let Some(p) = prev {
+                self.nodes[p].right = idx;
+                self.nodes[idx].left = p;
+            } else {
+                first = Some(idx);
+            }
+            prev = Some(idx);
+        }
+        let first = first.unwrap();
+        let last = prev.unwrap();
+        self.nodes[last].right = first;
+        self.nodes[first].left = last;
+        self.row_start[row_id] = first;
+    }
+
+    fn cover(&mut self, col: usize) {
+        let (left, right) = (self.nodes[col].left, self.nodes[col].right);
+        self.nodes[left].right = right;
+        self.nodes[right].left = left;
+
+        let mut i = self.nodes[col].down;
+        while i != col {
+            let mut j = self.nodes[i].right;
+            while j != i {
+                let (up, down) = (self.nodes[j].up, self.nodes[j].down);
+                self.nodes[up].down = down;
+                self.nodes[down].up = up;
+                self.size[self.nodes[j].column] -= 1;
+                j = self.nodes[j].right;
+            }
+            i = self.nodes[i].down;
+        }
+    }
+
+    fn uncover(&mut self, col: usize) {
+        let mut i = self.nodes[col].up;
+        while i != col {
+            let mut j = self.nodes[i].left;
+            while j != i {
+                self.size[self.nodes[j].column] += 1;
+                let (up, down) = (self.nodes[j].up, self.nodes[j].down);
+                self.nodes[up].down = j;
+                self.nodes[down].up = j;
+                j = self.nodes[j].left;
+            }
+            i = self.nodes[i].up;
+        }
+
+        let (left, right) = (self.nodes[col].left, self.nodes[col].right);
+        self.nodes[left].right = col;
+        self.nodes[right].left = col;
+    }
+
+    // cover a row that's already decided (a given), removing every row
+    // that conflicts with it from further consideration
+    fn cover_row(&mut self, row_id: usize) {
+        let start = self.row_start[row_id];
+        let mut j = start;
+        loop {
+            self.cover(self.nodes[j].column);
+            j = self.nodes[j].right;
+            if j == start {
+                break;
+            }
+        }
+    }
+
+    fn choose_column(&self) -> Option<usize> {
+        let mut col = self.nodes[ROOT].right;
+        if col == ROOT {
+            return None;
+        }
+        let mut best = col;
+        while col != ROOT {
+            if self.size[col] < self.size[best] {
+                best = col;
+            }
+            col = self.nodes[col].right;
+        }
+        Some(best)
+    }
+
+    fn search(&mut self, solution: &mut Vec<usize>) -> bool {
+        let col = match self.choose_column() {
+            None => return true,
+            Some(col) => col,
+        };
+
+        self.cover(col);
+        let mut row = self.nodes[col].down;
+        while row != col {
+            solution.push(self.nodes[row].row_id);
+
+            let mut j = self.nodes[row].right;
+            while j != row {
+                self.cover(self.nodes[j].column);
+                j = self.nodes[j].right;
+            }
+
+            if self.search(solution) {
+                return true;
+            }
+
+            let mut j = self.nodes[row].left;
+            while j != row {
+                self.uncover(self.nodes[j].column);
+                j = self.nodes[j].left;
+            }
+            solution.pop();
+
+            row = self.nodes[row].down;
+        }
+        self.uncover(col);
+        false
+    }
+}
+
+fn row_id(coord: Coord, num: Number) -> usize {
+    coord.0 as usize * 9 + (num.get() - 1) as usize
+}
+
+fn columns_for(coord: Coord, num: Number) -> [usize; 4] {
+    let x = coord.x() as usize;
+    let y = coord.y() as usize;
+    let sq = coord.square() as usize;
+    let d = (num.get() - 1) as usize;
+    [
+        y * 9 + x,
+        NUM_CELLS + y * 9 + d,
+        NUM_CELLS * 2 + x * 9 + d,
+        NUM_CELLS * 3 + sq * 9 + d,
+    ]
+}
+
+/// Solves the board by modeling it as an exact-cover problem and running
+/// Algorithm X over a dancing-links matrix, as a deterministic alternative
+/// to the recursive backtracker in `solve_helper`.
+pub fn solve_dlx(board: Board) -> Option<Board> {
+    let mut dlx = Dlx::new();
+    for coord in Coord::all(3) {
+        for n in 1..=9u8 {
+            let num = Number::new(n);
+            dlx.add_row(row_id(coord, num), columns_for(coord, num));
+        }
+    }
+
+    let mut solution = Vec::with_capacity(NUM_CELLS);
+    for coord in Coord::all(3) {
+        if let Space::Full(num) = board[coord] {
+            let id = row_id(coord, num);
+            dlx.cover_row(id);
+            solution.push(id);
+        }
+    }
+
+    if !dlx.search(&mut solution) {
+        return None;
+    }
+
+    let mut result = Board::new();
+    for id in solution {
+        let coord = Coord(id as u16 / 9, 3);
+        let num = Number::new((id % 9) as u8 + 1);
+        result[coord] = Space::Full(num);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::solve::solve;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000028000419005000080079";
+
+    #[test]
+    fn agrees_with_backtracking_solver() {
+        let board: Board = PUZZLE.parse().unwrap();
+        let expected = solve(board.clone()).unwrap();
+        let actual = solve_dlx(board).unwrap();
+        assert_eq!(actual.to_line_string(), expected.to_line_string());
+    }
+}
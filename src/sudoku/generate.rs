@@ -0,0 +1,113 @@
+// Puzzle generator: fill the three non-overlapping diagonal boxes with
+// random permutations (they share no row/column/box with each other, so
+// this can never conflict), hand the rest to the exact solver to
+// complete, then dig holes in centrally-symmetric pairs, keeping a
+// removal only while the puzzle stays uniquely solvable.
+
+use super::{Board, Coord, Number, Space, solve};
+use super::rng::Rng;
+use super::solve::count_solutions;
+
+#[derive(Copy, Clone)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    fn target_clues(&self) -> usize {
+        match self {
+            Self::Easy => 40,
+            Self::Medium => 32,
+            Self::Hard => 26,
+        }
+    }
+}
+
+fn fill_solved_grid(rng: &mut Rng) -> Board {
+    let mut board = Board::new();
+    // boxes 0, 4, 8 are the diagonal boxes for the classic 3x3 layout
+    for &square in &[0u16, 4, 8] {
+        let start_x = 3 * (square % 3);
+        let start_y = 3 * (square / 3);
+
+        let mut digits: Vec<u8> = (1..=9).collect();
+        rng.shuffle(&mut digits);
+        let mut digits = digits.into_iter();
+
+        for dy in 0..3u16 {
+            for dx in 0..3u16 {
+                let coord = Coord::classic(start_x + dx, start_y + dy);
+                board[coord] = Space::Full(Number::new(digits.next().unwrap()));
+            }
+        }
+    }
+
+    solve(board).expect("diagonal boxes never conflict, so the grid is always completable")
+}
+
+fn symmetric_partner(coord: Coord) -> Coord {
+    let side = coord.side();
+    Coord::new(side - 1 - coord.x(), side - 1 - coord.y(), coord.b())
+}
+
+/// Generates a full solved grid, then removes clues in centrally-symmetric
+/// pairs (falling back to single removals when a cell is its own partner),
+/// keeping each removal only if `count_solutions(..., 2) == 1` still holds.
+pub fn generate(difficulty: Difficulty) -> Board {
+    let mut rng = Rng::seeded();
+    let solved = fill_solved_grid(&mut rng);
+
+    let mut puzzle = solved.clone();
+    let mut order: Vec<Coord> = solved.coords().collect();
+    rng.shuffle(&mut order);
+
+    let target = difficulty.target_clues();
+    let mut remaining = puzzle.cells();
+
+    for coord in order {
+        if remaining <= target {
+            break;
+        }
+
+        if puzzle[coord] == Space::Empty {
+            // already removed as another cell's symmetric partner
+            continue;
+        }
+
+        let partner = symmetric_partner(coord);
+        let removed = puzzle[coord];
+        let removed_partner = (partner.x() != coord.x() || partner.y() != coord.y())
+            .then(|| (partner, puzzle[partner]));
+
+        puzzle[coord] = Space::Empty;
+        if let Some((p, _)) = removed_partner {
+            puzzle[p] = Space::Empty;
+        }
+
+        if count_solutions(puzzle.clone(), 2) == 1 {
+            remaining -= if removed_partner.is_some() { 2 } else { 1 };
+        } else {
+            puzzle[coord] = removed;
+            if let Some((p, value)) = removed_partner {
+                puzzle[p] = value;
+            }
+        }
+    }
+
+    puzzle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_puzzles_have_a_unique_solution() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let puzzle = generate(difficulty);
+            assert_eq!(count_solutions(puzzle, 2), 1);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+mod core;
+mod bitset;
+mod rng;
+mod solve;
+mod dlx;
+mod anneal;
+mod generate;
+
+pub use core::{Number, Space, Coord, Board};
+pub use solve::{solve, count_solutions};
+pub use dlx::solve_dlx;
+pub use anneal::solve_annealing;
+pub use generate::{generate, Difficulty};
@@ -0,0 +1,37 @@
+// Minimal self-contained PRNG (xorshift64) shared by the stochastic
+// solver and the puzzle generator, so neither needs an external crate.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(super) struct Rng(u64);
+
+impl Rng {
+    pub(super) fn seeded() -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().subsec_nanos() as u64;
+        Self(nanos | 1)
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    pub(super) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    pub(super) fn gen_range(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub(super) fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.gen_range(i + 1);
+            slice.swap(i, j);
+        }
+    }
+}
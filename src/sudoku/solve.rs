@@ -1,15 +1,16 @@
 use super::{Number, Space, Coord, Board};
+use super::bitset::Bitset;
 
 #[derive(Copy, Clone)]
-struct AllowedNumbers(u16);
+struct AllowedNumbers(u32);
 
 impl AllowedNumbers {
-    fn all() -> Self {
-        // trailing zero b/c numbers start at one
-        Self(0b111_111_111_0)
+    // trailing zero b/c numbers start at one
+    fn all(side: u16) -> Self {
+        Self(((1u32 << side) - 1) << 1)
     }
 
-    fn get_mask(num: Number) -> u16 {
+    fn get_mask(num: Number) -> u32 {
         1 << num.get()
     }
 
@@ -17,12 +18,20 @@ impl AllowedNumbers {
         self.0 &= !Self::get_mask(num);
     }
 
+    fn is_allowed(&self, num: Number) -> bool {
+        self.0 & Self::get_mask(num) != 0
+    }
+
     fn allowed(&self) -> AllowedNumbersIterator {
         AllowedNumbersIterator(self.0)
     }
+
+    fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
 }
 
-struct AllowedNumbersIterator(u16);
+struct AllowedNumbersIterator(u32);
 
 impl AllowedNumbersIterator {
     fn are_none_allowed(&self) -> bool {
@@ -36,7 +45,7 @@ impl AllowedNumbersIterator {
 
 impl Iterator for AllowedNumbersIterator {
     type Item = Number;
-    
+
     fn next(&mut self) -> Option<Number> {
         if self.0 == 0 {
             return None;
@@ -65,47 +74,79 @@ impl std::ops::BitAnd for AllowedNumbers {
     }
 }
 
-struct CoordBitsetIterator(u128);
-
-impl Iterator for CoordBitsetIterator {
-    type Item = Coord;
-    
-    fn next(&mut self) -> Option<Coord> {
-        if self.0 == 0 {
-            return None;
-        }
-        let lowest_set_bit = self.0.trailing_zeros();
-        // clear lowest bit
-        self.0 &= self.0 - 1;
-        Some(Coord(lowest_set_bit as u8))
-    }
-}
-
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 struct BookkeptBoard {
     board: Board,
-    open_spaces: u128,
-    updated_allows: u128,
-    col_allowed: [AllowedNumbers; 9],
-    row_allowed: [AllowedNumbers; 9],
-    box_allowed: [AllowedNumbers; 9],
+    b: u8,
+    open_spaces: Bitset,
+    updated_allows: Bitset,
+    col_allowed: Vec<AllowedNumbers>,
+    row_allowed: Vec<AllowedNumbers>,
+    box_allowed: Vec<AllowedNumbers>,
+    // candidates[idx] caches col_allowed & row_allowed & box_allowed for
+    // that cell, minus any digits removed by locked candidates, so
+    // pick_open_space can compare candidate counts without re-deriving
+    // them from the three region masks at every call.
+    candidates: Vec<AllowedNumbers>,
+    col_masks: Vec<Bitset>,
+    row_masks: Vec<Bitset>,
+    box_masks: Vec<Bitset>,
 }
 
 impl BookkeptBoard {
-    fn new() -> Self {
+    fn new(b: u8) -> Self {
+        let side = b as u16 * b as u16;
+        let cells = side as usize * side as usize;
+
+        let mut col_masks = Vec::with_capacity(side as usize);
+        for x in 0..side as usize {
+            let mut mask = Bitset::new(cells);
+            for y in 0..side as usize {
+                mask.set(y * side as usize + x);
+            }
+            col_masks.push(mask);
+        }
+
+        let mut row_masks = Vec::with_capacity(side as usize);
+        for y in 0..side as usize {
+            let mut mask = Bitset::new(cells);
+            for x in 0..side as usize {
+                mask.set(y * side as usize + x);
+            }
+            row_masks.push(mask);
+        }
+
+        let mut box_masks = Vec::with_capacity(side as usize);
+        for square in 0..side as usize {
+            let mut mask = Bitset::new(cells);
+            let start_x = b as usize * (square % b as usize);
+            let start_y = b as usize * (square / b as usize);
+            for dy in 0..b as usize {
+                for dx in 0..b as usize {
+                    mask.set((start_y + dy) * side as usize + (start_x + dx));
+                }
+            }
+            box_masks.push(mask);
+        }
+
         Self {
-            board: Board::new(),
-            open_spaces: (1 << 81) - 1,
-            updated_allows: 0,
-            col_allowed: [AllowedNumbers::all(); 9],
-            row_allowed: [AllowedNumbers::all(); 9],
-            box_allowed: [AllowedNumbers::all(); 9]
+            board: Board::with_box_size(b),
+            b,
+            open_spaces: Bitset::full(cells),
+            updated_allows: Bitset::new(cells),
+            col_allowed: vec![AllowedNumbers::all(side); side as usize],
+            row_allowed: vec![AllowedNumbers::all(side); side as usize],
+            box_allowed: vec![AllowedNumbers::all(side); side as usize],
+            candidates: vec![AllowedNumbers::all(side); cells],
+            col_masks,
+            row_masks,
+            box_masks,
         }
     }
-    
+
     fn from_board(board: Board) -> Self {
-        let mut res = Self::new();
-        for coord in Coord::all() {
+        let mut res = Self::new(board.b());
+        for coord in board.coords() {
             if let Space::Full(num) = board[coord] {
                 res.fill(coord, num);
             }
@@ -115,75 +156,219 @@ impl BookkeptBoard {
 
     fn fill(&mut self, coord: Coord, num: Number) {
         assert!(self.board[coord] == Space::Empty);
-            
+
         self.board[coord] = Space::Full(num);
-            
-        self.open_spaces &= !(1 << coord.0);
-        
-        self.add_col_updates(coord);
-        self.add_row_updates(coord);
-        self.add_box_updates(coord);
-        self.updated_allows &= self.open_spaces;
-
-        self.col_allowed[coord.x() as usize].disallow(num);
-        self.row_allowed[coord.y() as usize].disallow(num);
-        self.box_allowed[coord.square() as usize].disallow(num);
-    }
-
-    fn add_col_updates(&mut self, coord: Coord) {
-        const COL_MASK: u128 = 
-            0b000_000_001 << 72 |
-            0b000_000_001 << 63 |
-            0b000_000_001 << 54 |
-            0b000_000_001 << 45 |
-            0b000_000_001 << 36 |
-            0b000_000_001 << 27 |
-            0b000_000_001 << 18 |
-            0b000_000_001 << 9  |
-            0b000_000_001;
-        // let this_col_mask = COL_MASK << coord.x();
-        const COL_MASKS: [u128; 9] = [COL_MASK, COL_MASK << 1, COL_MASK << 2, COL_MASK << 3, COL_MASK << 4, COL_MASK << 5, COL_MASK << 6, COL_MASK << 7, COL_MASK << 8];
-        let this_col_mask = COL_MASKS[coord.x() as usize];
-        self.updated_allows |= this_col_mask;
-    }
-    
-    fn add_row_updates(&mut self, coord: Coord) {
-        const ROW_MASK: u128 = 0b111_111_111;
-        // let this_row_mask = ROW_MASK << (coord.y() * 9);
-        const ROW_MASKS: [u128; 9] = [ROW_MASK, ROW_MASK << 9, ROW_MASK << 18, ROW_MASK << 27, ROW_MASK << 36, ROW_MASK << 45, ROW_MASK << 54, ROW_MASK << 63, ROW_MASK << 72];
-        let this_row_mask = ROW_MASKS[coord.y() as usize];
-        self.updated_allows |= this_row_mask;
-    }
-
-    fn add_box_updates(&mut self, coord: Coord) {
-        const BOX_MASK: u128 = 
-            0b000_000_111 << 18 |
-            0b000_000_111 << 9  |
-            0b000_000_111;
-        let shift = 3 * (coord.x() / 3) + 27 * (coord.y() / 3);
-        let this_box_mask = BOX_MASK << shift;
-        self.updated_allows |= this_box_mask;
-    }
-
-    fn open_spaces(&self) -> impl Iterator<Item=Coord> {
-        CoordBitsetIterator(self.open_spaces)
+
+        let idx = coord.0 as usize;
+        self.open_spaces.clear(idx);
+
+        let x = coord.x() as usize;
+        let y = coord.y() as usize;
+        let square = coord.square() as usize;
+
+        let mut touched = self.col_masks[x].clone();
+        touched.union_with(&self.row_masks[y]);
+        touched.union_with(&self.box_masks[square]);
+
+        self.updated_allows.union_with(&touched);
+        self.updated_allows.intersect_with(&self.open_spaces);
+
+        self.col_allowed[x].disallow(num);
+        self.row_allowed[y].disallow(num);
+        self.box_allowed[square].disallow(num);
+
+        touched.intersect_with(&self.open_spaces);
+        self.recompute_candidates(&touched);
+    }
+
+    // recomputes the cached candidate set for every still-open coord in
+    // `mask`, so a fill only touches the cells in its row/col/box instead
+    // of rescanning the whole board.
+    fn recompute_candidates(&mut self, mask: &Bitset) {
+        for idx in mask.iter() {
+            let coord = Coord(idx as u16, self.b);
+            self.candidates[idx] = self.candidates[idx]
+                & self.col_allowed[coord.x() as usize]
+                & self.row_allowed[coord.y() as usize]
+                & self.box_allowed[coord.square() as usize];
+        }
+    }
+
+    fn open_spaces(&self) -> impl Iterator<Item=Coord> + '_ {
+        let b = self.b;
+        self.open_spaces.iter().map(move |idx| Coord(idx as u16, b))
     }
 
     fn any_updates(&self) -> bool {
-        self.updated_allows != 0
+        !self.updated_allows.is_empty()
     }
-    
+
     fn take_updates(&mut self) -> impl Iterator<Item=Coord> {
-        let updated = self.updated_allows;
-        self.updated_allows = 0;
-        CoordBitsetIterator(updated)
+        let updated = self.updated_allows.iter();
+        self.updated_allows.clear_all();
+        let b = self.b;
+        updated.map(move |idx| Coord(idx as u16, b))
     }
 
     fn allowed(&self, coord: Coord) -> AllowedNumbersIterator {
-        let mut res = self.col_allowed[coord.x() as usize];
-        res = res & self.row_allowed[coord.y() as usize];
-        res = res & self.box_allowed[coord.square() as usize];
-        res.allowed()
+        self.candidates[coord.0 as usize].allowed()
+    }
+
+    // hidden single: a digit that can only go in one open cell of a
+    // region, even though that cell has other candidates too. Also
+    // detects the contradiction where a still-needed digit has nowhere
+    // left to go in the region.
+    fn hidden_singles_in_region(&mut self, region_mask: &Bitset, region_allowed: AllowedNumbers) -> Option<bool> {
+        let mut changed = false;
+        let coords: Vec<Coord> = region_mask.iter().map(|idx| Coord(idx as u16, self.b)).collect();
+
+        for num in region_allowed.allowed() {
+            let mut found = None;
+            let mut count = 0u8;
+            for &c in &coords {
+                if self.board[c] != Space::Empty {
+                    continue;
+                }
+                if self.candidates[c.0 as usize].is_allowed(num) {
+                    count += 1;
+                    found = Some(c);
+                    if count > 1 {
+                        break;
+                    }
+                }
+            }
+
+            if count == 0 {
+                return None;
+            } else if count == 1 {
+                let coord = found.unwrap();
+                if self.board[coord] == Space::Empty {
+                    self.fill(coord, num);
+                    changed = true;
+                }
+            }
+        }
+
+        Some(changed)
+    }
+
+    fn apply_hidden_singles(&mut self) -> Option<bool> {
+        let mut changed = false;
+        let side = self.col_allowed.len();
+
+        for x in 0..side {
+            let mask = self.col_masks[x].clone();
+            if self.hidden_singles_in_region(&mask, self.col_allowed[x])? {
+                changed = true;
+            }
+        }
+        for y in 0..side {
+            let mask = self.row_masks[y].clone();
+            if self.hidden_singles_in_region(&mask, self.row_allowed[y])? {
+                changed = true;
+            }
+        }
+        for square in 0..side {
+            let mask = self.box_masks[square].clone();
+            if self.hidden_singles_in_region(&mask, self.box_allowed[square])? {
+                changed = true;
+            }
+        }
+
+        Some(changed)
+    }
+
+    fn cells_with_candidate(&self, mask: &Bitset, num: Number) -> Vec<Coord> {
+        mask.iter()
+            .map(|idx| Coord(idx as u16, self.b))
+            .filter(|c| self.board[*c] == Space::Empty && self.candidates[c.0 as usize].is_allowed(num))
+            .collect()
+    }
+
+    // removes `num` from every open cell in `mask` that isn't also in
+    // `except`, marking any changed cell for naked-single reprocessing
+    fn eliminate_from_mask_except(&mut self, mask: &Bitset, except: &Bitset, num: Number) -> bool {
+        let mut changed = false;
+        for idx in mask.iter() {
+            if except.get(idx) {
+                continue;
+            }
+            let coord = Coord(idx as u16, self.b);
+            if self.board[coord] == Space::Empty && self.candidates[idx].is_allowed(num) {
+                self.candidates[idx].disallow(num);
+                self.updated_allows.set(idx);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    // locked candidates: if a digit's candidates within a box all lie in
+    // one row/column (pointing), or a row/column's candidates all lie in
+    // one box (claiming), the digit can be removed from the rest of the
+    // other region.
+    fn apply_locked_candidates(&mut self) -> bool {
+        let mut changed = false;
+        let side = self.col_allowed.len();
+
+        for square in 0..side {
+            let box_mask = self.box_masks[square].clone();
+            for num in self.box_allowed[square].allowed() {
+                let cells = self.cells_with_candidate(&box_mask, num);
+                let first = match cells.first() {
+                    None => continue,
+                    Some(&c) => c,
+                };
+                if cells.iter().all(|c| c.y() == first.y()) {
+                    let row_mask = self.row_masks[first.y() as usize].clone();
+                    changed |= self.eliminate_from_mask_except(&row_mask, &box_mask, num);
+                } else if cells.iter().all(|c| c.x() == first.x()) {
+                    let col_mask = self.col_masks[first.x() as usize].clone();
+                    changed |= self.eliminate_from_mask_except(&col_mask, &box_mask, num);
+                }
+            }
+        }
+
+        for y in 0..side {
+            let row_mask = self.row_masks[y].clone();
+            for num in self.row_allowed[y].allowed() {
+                let cells = self.cells_with_candidate(&row_mask, num);
+                let first = match cells.first() {
+                    None => continue,
+                    Some(&c) => c,
+                };
+                if cells.iter().all(|c| c.square() == first.square()) {
+                    let box_mask = self.box_masks[first.square() as usize].clone();
+                    changed |= self.eliminate_from_mask_except(&box_mask, &row_mask, num);
+                }
+            }
+        }
+
+        for x in 0..side {
+            let col_mask = self.col_masks[x].clone();
+            for num in self.col_allowed[x].allowed() {
+                let cells = self.cells_with_candidate(&col_mask, num);
+                let first = match cells.first() {
+                    None => continue,
+                    Some(&c) => c,
+                };
+                if cells.iter().all(|c| c.square() == first.square()) {
+                    let box_mask = self.box_masks[first.square() as usize].clone();
+                    changed |= self.eliminate_from_mask_except(&box_mask, &col_mask, num);
+                }
+            }
+        }
+
+        changed
+    }
+
+    // one round of the "next-cheapest" human techniques beyond naked
+    // singles; returns None on the contradiction a hidden single check
+    // can uncover (a region digit with nowhere left to go)
+    fn apply_region_techniques(&mut self) -> Option<bool> {
+        let hidden = self.apply_hidden_singles()?;
+        let locked = self.apply_locked_candidates();
+        Some(hidden || locked)
     }
 }
 
@@ -195,29 +380,36 @@ impl std::ops::Index<Coord> for BookkeptBoard {
 }
 
 fn make_forced_choices(mut board: BookkeptBoard) -> Option<BookkeptBoard> {
-    while board.any_updates() {
-        for update in board.take_updates() {
-            let mut allowed = board.allowed(update);
-            if allowed.are_none_allowed() {
-                return None;
-            } else if allowed.is_one_allowed() {
-                let num = allowed.next().unwrap();
-                board.fill(update, num);
+    loop {
+        while board.any_updates() {
+            for update in board.take_updates() {
+                let mut allowed = board.allowed(update);
+                if allowed.are_none_allowed() {
+                    return None;
+                } else if allowed.is_one_allowed() {
+                    let num = allowed.next().unwrap();
+                    board.fill(update, num);
+                }
             }
         }
+
+        // naked singles are exhausted; try the pricier region-wide
+        // techniques, and only stop once neither makes progress
+        if board.apply_region_techniques()? {
+            continue;
+        }
+
+        return Some(board);
     }
-    // checked the whole board, didn't fill any squares
-    // so we're done
-    return Some(board);
 }
 
 fn pick_open_space(board: &BookkeptBoard) -> Option<Coord> {
-    // i want to pick one with the least possibilities
-    // but not sure how to do it fast
-    board.open_spaces().next()
+    // most-constrained-cell: the fewer candidates a cell has, the fewer
+    // branches we create, so pick the open cell with the smallest count
+    board.open_spaces()
+        .min_by_key(|coord| board.candidates[coord.0 as usize].count_ones())
 }
 
-
 fn solve_helper(mut board: BookkeptBoard) -> Option<BookkeptBoard> {
     board = make_forced_choices(board)?;
     let open_space = match pick_open_space(&board) {
@@ -228,7 +420,7 @@ fn solve_helper(mut board: BookkeptBoard) -> Option<BookkeptBoard> {
     let allowed = board.allowed(open_space);
 
     for possibility in allowed {
-        let mut possible_board = board;
+        let mut possible_board = board.clone();
         possible_board.fill(open_space, possibility);
         if let Some(solved) = solve_helper(possible_board) {
             return Some(solved);
@@ -241,4 +433,37 @@ pub fn solve(board: Board) -> Option<Board> {
     let board = BookkeptBoard::from_board(board);
     let res = solve_helper(board)?;
     Some(res.board)
-}
\ No newline at end of file
+}
+
+// keeps recursing past the first solution, stopping once `cap` solutions
+// have been found (so `count_solutions(board, 2) > 1` tests ambiguity)
+fn count_solutions_helper(board: BookkeptBoard, cap: usize, count: &mut usize) {
+    let board = match make_forced_choices(board) {
+        None => return,
+        Some(board) => board,
+    };
+
+    let open_space = match pick_open_space(&board) {
+        None => {
+            *count += 1;
+            return;
+        }
+        Some(space) => space,
+    };
+
+    for possibility in board.allowed(open_space) {
+        if *count >= cap {
+            return;
+        }
+        let mut possible_board = board.clone();
+        possible_board.fill(open_space, possibility);
+        count_solutions_helper(possible_board, cap, count);
+    }
+}
+
+pub fn count_solutions(board: Board, cap: usize) -> usize {
+    let board = BookkeptBoard::from_board(board);
+    let mut count = 0;
+    count_solutions_helper(board, cap, &mut count);
+    count
+}